@@ -81,6 +81,28 @@
 //!   std::sync::Mutex::nest_try_lock
 //!   std::sync::RwLock::nest_try_read
 //!   std::sync::RwLock::nest_try_write
+//!   std::sync::RwLock::nest_read
+//!   std::sync::RwLock::nest_write
+//!   std::cell::OnceCell::nest_get
+//!   std::cell::OnceCell::nest_get_or_init
+//!   std::sync::OnceLock::nest_get
+//!   std::sync::OnceLock::nest_get_or_init
+//!
+//! `std::cell::Cell` is not covered: its only accessors take `T` by value
+//! or `&mut self`, neither of which produces a borrow that `Nested` could
+//! keep alive, so it doesn't fit this crate's guard-borrowing model.
+//!
+//! `Nested` also supports projecting onto a subfield of a `RefCell`'s
+//! contents, via `map`/`map_mut`/`nest_map_split`/`nest_map_split_mut`,
+//! mirroring `Ref::map`/`RefMut::map`/`Ref::map_split`/`RefMut::map_split`.
+//! There is no equivalent for `Mutex`/`RwLock` guards, since the standard
+//! library does not expose a stable `map` on `MutexGuard`/`RwLockReadGuard`.
+//!
+//! `nest_borrow`, `nest_borrow_mut` and `nest_get_or_init` are thin wrappers
+//! over the generic `NestGuard::nest_with`; the fallible and lock-based
+//! `nest_*` methods build their `Nested` directly. `nest_with` is `pub` so
+//! that third-party interior-mutability types can build their own `nest_*`
+//! extension traits without living in this crate.
 
 use std::ops::{Deref, DerefMut};
 
@@ -116,16 +138,42 @@ unsafe fn remove_lifetime<'b, T>(x: &T) -> &'b T {
     unsafe { &*(x as *const T) }
 }
 
+/// A generic form of the outer/inner bundling that the `nest_*` extension
+/// traits in this crate perform. Implemented for every `Deref`, so custom
+/// interior-mutability types
+/// outside this crate (e.g. shred's `TrustCell`) can build their own
+/// `nest_*` methods on top of it without needing to live in this crate.
+pub trait NestGuard: Deref + Sized {
+    /// Produce an `Inner` guard that borrows from `self`'s target, and bundle
+    /// it together with `self` so the latter outlives the former.
+    ///
+    /// # Safety contract
+    ///   `f` must return a guard that only borrows from the `&Self::Target`
+    ///   it is given, and nothing else. Violating this lets the returned
+    ///   `Nested` outlive data it actually borrows from.
+    fn nest_with<'a, Inner, F>(self, f: F) -> Nested<Inner::Target, Inner, Self>
+    where
+        Self: 'a,
+        Inner: Deref,
+        Inner::Target: Sized,
+        F: FnOnce(&'a Self::Target) -> Inner,
+    {
+        let me = unsafe { remove_lifetime(&self) };
+        let inner = f(me);
+        Nested { inner, outer: self }
+    }
+}
+impl<T: Deref + Sized> NestGuard for T {}
+
 mod cell {
     use std::cell::*;
+    use std::rc::Rc;
 
     use super::*;
 
     pub trait NestedRefCell<'a, T>: Deref<Target = RefCell<T>> + Sized + 'a {
         fn nest_borrow(self) -> Nested<T, Ref<'a, T>, Self> {
-            let me = unsafe { remove_lifetime(&self) };
-            let inner = RefCell::borrow(me);
-            Nested { inner, outer: self }
+            self.nest_with(RefCell::borrow)
         }
         fn nest_try_borrow(self) -> Result<Nested<T, Ref<'a, T>, Self>, BorrowError> {
             let me = unsafe { remove_lifetime(&self) };
@@ -133,9 +181,7 @@ mod cell {
             Ok(Nested { inner, outer: self })
         }
         fn nest_borrow_mut(self) -> Nested<T, RefMut<'a, T>, Self> {
-            let me = unsafe { remove_lifetime(&self) };
-            let inner = RefCell::borrow_mut(me);
-            Nested { inner, outer: self }
+            self.nest_with(RefCell::borrow_mut)
         }
         fn nest_try_borrow_mut(self) -> Result<Nested<T, RefMut<'a, T>, Self>, BorrowMutError> {
             let me = unsafe { remove_lifetime(&self) };
@@ -145,10 +191,152 @@ mod cell {
     }
     impl<'a, T, Outer: Deref<Target = RefCell<T>> + Sized + 'a> NestedRefCell<'a, T> for Outer {}
 
+    pub trait NestedOnceCell<'a, T>: Deref<Target = OnceCell<T>> + Sized + 'a {
+        fn nest_get(self) -> Option<Nested<T, &'a T, Self>> {
+            let me = unsafe { remove_lifetime(&self) };
+            let inner = me.get()?;
+            Some(Nested { inner, outer: self })
+        }
+        fn nest_get_or_init<F: FnOnce() -> T>(self, f: F) -> Nested<T, &'a T, Self> {
+            self.nest_with(|cell| cell.get_or_init(f))
+        }
+    }
+    impl<'a, T, Outer: Deref<Target = OnceCell<T>> + Sized + 'a> NestedOnceCell<'a, T> for Outer {}
+
+    /// Pair of guards produced by `nest_map_split`, sharing one `Rc`-wrapped
+    /// `outer`.
+    type SplitPair<'a, U, V, Outer> = (
+        Nested<U, Ref<'a, U>, Rc<Outer>>,
+        Nested<V, Ref<'a, V>, Rc<Outer>>,
+    );
+
+    impl<'a, T, Outer> Nested<T, Ref<'a, T>, Outer> {
+        /// Like `Ref::map`, but keeps the whole `outer`/`inner` stack alive
+        /// while projecting the inner `Ref` onto a subfield of `T`.
+        pub fn map<U, F: FnOnce(&T) -> &U>(self, f: F) -> Nested<U, Ref<'a, U>, Outer> {
+            let Nested { inner, outer } = self;
+            Nested {
+                inner: Ref::map(inner, f),
+                outer,
+            }
+        }
+
+        /// Like `Ref::map_split`, but keeps the `outer` temporary alive for
+        /// both derived guards by sharing it behind an `Rc`. `outer` is only
+        /// dropped once both halves have been dropped.
+        pub fn nest_map_split<U, V, F: FnOnce(&T) -> (&U, &V)>(
+            self,
+            f: F,
+        ) -> SplitPair<'a, U, V, Outer> {
+            let Nested { inner, outer } = self;
+            let outer = Rc::new(outer);
+            let (a, b) = Ref::map_split(inner, f);
+            (
+                Nested {
+                    inner: a,
+                    outer: outer.clone(),
+                },
+                Nested { inner: b, outer },
+            )
+        }
+    }
+    /// Pair of guards produced by `nest_map_split_mut`, sharing one
+    /// `Rc`-wrapped `outer`.
+    type SplitPairMut<'a, U, V, Outer> = (
+        Nested<U, RefMut<'a, U>, Rc<Outer>>,
+        Nested<V, RefMut<'a, V>, Rc<Outer>>,
+    );
+
+    impl<'a, T, Outer> Nested<T, RefMut<'a, T>, Outer> {
+        /// Like `RefMut::map`, but keeps the whole `outer`/`inner` stack alive
+        /// while projecting the inner `RefMut` onto a subfield of `T`.
+        pub fn map_mut<U, F: FnOnce(&mut T) -> &mut U>(
+            self,
+            f: F,
+        ) -> Nested<U, RefMut<'a, U>, Outer> {
+            let Nested { inner, outer } = self;
+            Nested {
+                inner: RefMut::map(inner, f),
+                outer,
+            }
+        }
+
+        /// Like `RefMut::map_split`, but keeps the `outer` temporary alive
+        /// for both derived guards by sharing it behind an `Rc`. `outer` is
+        /// only dropped once both halves have been dropped.
+        pub fn nest_map_split_mut<U, V, F: FnOnce(&mut T) -> (&mut U, &mut V)>(
+            self,
+            f: F,
+        ) -> SplitPairMut<'a, U, V, Outer> {
+            let Nested { inner, outer } = self;
+            let outer = Rc::new(outer);
+            let (a, b) = RefMut::map_split(inner, f);
+            (
+                Nested {
+                    inner: a,
+                    outer: outer.clone(),
+                },
+                Nested { inner: b, outer },
+            )
+        }
+    }
+
     #[cfg(test)]
     mod test {
         use super::*;
 
+        #[test]
+        fn test_many_once_cell() {
+            let x = OnceCell::new();
+            assert!(x.nest_get().is_none());
+            x.set(OnceCell::new()).ok().unwrap();
+            {
+                let z = x.get().unwrap().nest_get_or_init(|| 0);
+                assert_eq!(0, *z);
+            }
+            {
+                let z = x.nest_get().unwrap().nest_get_or_init(|| 1);
+                assert_eq!(0, *z);
+            }
+        }
+
+        #[test]
+        fn test_map() {
+            let x = RefCell::new(RefCell::new((0, 1)));
+            {
+                let z = x.borrow().nest_borrow().map(|pair| &pair.0);
+                assert_eq!(0, *z);
+            }
+            {
+                let mut z = x.borrow().nest_borrow_mut().map_mut(|pair| &mut pair.1);
+                *z = 2;
+                assert_eq!(2, *z);
+            }
+            assert_eq!((0, 2), *x.borrow().nest_borrow());
+        }
+
+        #[test]
+        fn test_map_split() {
+            let x = RefCell::new(RefCell::new((0, 1)));
+            {
+                let (a, b) = x
+                    .borrow()
+                    .nest_borrow()
+                    .nest_map_split(|pair| (&pair.0, &pair.1));
+                assert_eq!(0, *a);
+                assert_eq!(1, *b);
+            }
+            {
+                let (mut a, mut b) = x
+                    .borrow()
+                    .nest_borrow_mut()
+                    .nest_map_split_mut(|pair| (&mut pair.0, &mut pair.1));
+                *a = 2;
+                *b = 3;
+            }
+            assert_eq!((2, 3), *x.borrow().nest_borrow());
+        }
+
         #[test]
         fn test_many_refcell() {
             let x = RefCell::new(RefCell::new(RefCell::new(0)));
@@ -234,6 +422,18 @@ mod sync {
     }
     impl<'a, T, Outer: Deref<Target = Weak<T>> + Sized + 'a> NestedArcWeak<'a, T> for Outer {}
 
+    pub trait NestedOnceLock<'a, T>: Deref<Target = OnceLock<T>> + Sized + 'a {
+        fn nest_get(self) -> Option<Nested<T, &'a T, Self>> {
+            let me = unsafe { remove_lifetime(&self) };
+            let inner = me.get()?;
+            Some(Nested { inner, outer: self })
+        }
+        fn nest_get_or_init<F: FnOnce() -> T>(self, f: F) -> Nested<T, &'a T, Self> {
+            self.nest_with(|cell| cell.get_or_init(f))
+        }
+    }
+    impl<'a, T, Outer: Deref<Target = OnceLock<T>> + Sized + 'a> NestedOnceLock<'a, T> for Outer {}
+
     pub trait NestedMutex<'a, T>: Deref<Target = Mutex<T>> + Sized + 'a {
         fn nest_lock(self) -> LockResult<Nested<T, MutexGuard<'a, T>, Self>> {
             let me = unsafe { remove_lifetime(&self) };
@@ -265,6 +465,26 @@ mod sync {
     impl<'a, T, Outer: Deref<Target = Mutex<T>> + Sized + 'a> NestedMutex<'a, T> for Outer {}
 
     pub trait NestedRwLock<'a, T>: Deref<Target = RwLock<T>> + Sized + 'a {
+        fn nest_read(self) -> LockResult<Nested<T, RwLockReadGuard<'a, T>, Self>> {
+            let me = unsafe { remove_lifetime(&self) };
+            match me.read() {
+                Ok(inner) => Ok(Nested { inner, outer: self }),
+                Err(err) => {
+                    let inner = err.into_inner();
+                    Err(PoisonError::new(Nested { inner, outer: self }))
+                }
+            }
+        }
+        fn nest_write(self) -> LockResult<Nested<T, RwLockWriteGuard<'a, T>, Self>> {
+            let me = unsafe { remove_lifetime(&self) };
+            match me.write() {
+                Ok(inner) => Ok(Nested { inner, outer: self }),
+                Err(err) => {
+                    let inner = err.into_inner();
+                    Err(PoisonError::new(Nested { inner, outer: self }))
+                }
+            }
+        }
         fn nest_try_read(self) -> TryLockResult<Nested<T, RwLockReadGuard<'a, T>, Self>> {
             let me = unsafe { remove_lifetime(&self) };
             match me.try_read() {
@@ -304,6 +524,21 @@ mod sync {
     mod test {
         use super::*;
 
+        #[test]
+        fn test_many_once_lock() {
+            let x = OnceLock::new();
+            assert!(x.nest_get().is_none());
+            x.set(OnceLock::new()).ok().unwrap();
+            {
+                let z = x.get().unwrap().nest_get_or_init(|| 0);
+                assert_eq!(0, *z);
+            }
+            {
+                let z = x.nest_get().unwrap().nest_get_or_init(|| 1);
+                assert_eq!(0, *z);
+            }
+        }
+
         #[test]
         fn test_many_arc() {
             let x1: Arc<i32> = Arc::new(0);
@@ -375,6 +610,27 @@ mod sync {
             }
         }
         #[test]
+        fn test_many_rwlock_blocking() {
+            let x = RwLock::new(RwLock::new(RwLock::new(0)));
+            {
+                let z = x.read().unwrap().nest_read().unwrap().nest_read().unwrap();
+                assert_eq!(0, *z);
+            }
+            {
+                let mut z = x.read().unwrap().nest_read().unwrap().nest_write().unwrap();
+                *z = 1;
+                assert_eq!(1, *z);
+            }
+            {
+                {
+                    let mut y = x.read().unwrap().nest_write().unwrap();
+                    *y = RwLock::new(2);
+                }
+                let z = x.read().unwrap().nest_read().unwrap().nest_read().unwrap();
+                assert_eq!(2, *z);
+            }
+        }
+        #[test]
         fn test_many_mutex() {
             let x = RwLock::new(RwLock::new(RwLock::new(0)));
             {
@@ -445,6 +701,13 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_nest_with() {
+        let x = RefCell::new(RefCell::new(0));
+        let z = x.borrow().nest_with(RefCell::borrow);
+        assert_eq!(0, *z);
+    }
+
     // #[test]
     // fn test_reassign_refcell_stack_does_not_compile() {
     //   let x = RefCell::new(RefCell::new(0));